@@ -94,16 +94,25 @@ use core::{fmt::Write, ops::Deref};
 ///
 /// The buffer maintains an internal cursor position and validates all writes to ensure
 /// UTF-8 correctness and capacity constraints.
+///
+/// # Span tracking
+///
+/// The optional second const generic `S` reserves room for `S` out-of-band [`Span`]
+/// tags recorded via [`span_start`] and [`span_end`]. It defaults to `0`, so buffers
+/// that do not need markup tracking keep their original single-parameter form.
+///
+/// [`span_start`]: StrBuf::span_start
+/// [`span_end`]: StrBuf::span_end
 #[derive(Copy, Clone, Debug)]
-pub struct StrBuf<const N: usize>([u8; N], usize);
+pub struct StrBuf<const N: usize, const S: usize = 0>([u8; N], usize, [Span; S], usize, usize);
 
-impl<const N: usize> Default for StrBuf<N> {
+impl<const N: usize, const S: usize> Default for StrBuf<N, S> {
     fn default() -> Self {
-        Self([0; N], 0)
+        Self([0; N], 0, [Span::EMPTY; S], 0, 0)
     }
 }
 
-impl<const N: usize> Deref for StrBuf<N> {
+impl<const N: usize, const S: usize> Deref for StrBuf<N, S> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -111,13 +120,13 @@ impl<const N: usize> Deref for StrBuf<N> {
     }
 }
 
-impl<const N: usize> AsRef<str> for StrBuf<N> {
+impl<const N: usize, const S: usize> AsRef<str> for StrBuf<N, S> {
     fn as_ref(&self) -> &str {
         self.deref()
     }
 }
 
-impl<const N: usize> Write for StrBuf<N> {
+impl<const N: usize, const S: usize> Write for StrBuf<N, S> {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
         let (.., free) = self.0.split_at_mut(self.1);
         if s.len() > free.len() {
@@ -131,7 +140,7 @@ impl<const N: usize> Write for StrBuf<N> {
     }
 }
 
-impl<const N: usize> StrBuf<N> {
+impl<const N: usize, const S: usize> StrBuf<N, S> {
     /// Creates a new buffer containing the formatted string representation of a value.
     ///
     /// # Arguments
@@ -141,7 +150,7 @@ impl<const N: usize> StrBuf<N> {
     /// # Errors
     ///
     /// Returns `core::fmt::Error` if formatting fails or if the buffer is too small.
-    pub fn display<T: core::fmt::Display>(value: T) -> Result<StrBuf<N>, core::fmt::Error> {
+    pub fn display<T: core::fmt::Display>(value: T) -> Result<StrBuf<N, S>, core::fmt::Error> {
         let mut buf = StrBuf::default();
         write!(buf, "{}", value)?;
         Ok(buf)
@@ -169,9 +178,462 @@ impl<const N: usize> StrBuf<N> {
     /// let buf = StrBuf::<128>::format(format_args!("Hello, {}!", name)).unwrap();
     /// assert_eq!(buf.as_ref(), "Hello, world!");
     /// ```
-    pub fn format(args: core::fmt::Arguments<'_>) -> Result<StrBuf<N>, core::fmt::Error> {
+    pub fn format(args: core::fmt::Arguments<'_>) -> Result<StrBuf<N, S>, core::fmt::Error> {
         let mut buf = StrBuf::default();
         buf.write_fmt(args)?;
         Ok(buf)
     }
+
+    /// Creates a new, empty buffer in a `const` context.
+    ///
+    /// Unlike [`Default::default`], this is a `const fn`, so it can be used together
+    /// with [`push_str`] and [`as_str`] to assemble fixed strings in `const`
+    /// initializers with no runtime cost.
+    ///
+    /// [`push_str`]: StrBuf::push_str
+    /// [`as_str`]: StrBuf::as_str
+    pub const fn new() -> Self {
+        Self([0; N], 0, [Span::EMPTY; S], 0, 0)
+    }
+
+    /// Appends `s` to the buffer in a `const` context.
+    ///
+    /// Bytes are copied with a manual index loop, since `copy_from_slice` is not
+    /// available in `const`. Use this to build configuration strings, banners, or
+    /// lookup tables at compile time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` containing the total byte length that would be required — the
+    /// current length plus `s` — when that exceeds the capacity `N`. The buffer is
+    /// left unchanged in that case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use strbuf::StrBuf;
+    ///
+    /// const BANNER: StrBuf<16> = {
+    ///     let mut buf = StrBuf::new();
+    ///     // `unwrap` is not const here, so match and discard the Ok value.
+    ///     match buf.push_str("strbuf v1") {
+    ///         Ok(()) => {}
+    ///         Err(_) => {}
+    ///     }
+    ///     buf
+    /// };
+    /// assert_eq!(BANNER.as_str(), "strbuf v1");
+    /// ```
+    pub const fn push_str(&mut self, s: &str) -> Result<(), usize> {
+        let bytes = s.as_bytes();
+        let required = self.1 + bytes.len();
+        if required > N {
+            return Err(required);
+        }
+
+        let mut i = 0;
+        while i < bytes.len() {
+            self.0[self.1 + i] = bytes[i];
+            i += 1;
+        }
+        self.1 = required;
+        Ok(())
+    }
+
+    /// Returns the written contents as a string slice in a `const` context.
+    ///
+    /// This is the `const fn` counterpart of the [`Deref`] to `str`. The buffer is
+    /// always valid UTF-8 because [`push_str`] only ever copies whole `&str`s.
+    ///
+    /// [`Deref`]: core::ops::Deref
+    /// [`push_str`]: StrBuf::push_str
+    pub const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.0.split_at(self.1).0) {
+            Ok(s) => s,
+            Err(_) => "",
+        }
+    }
+
+    /// Returns the current cursor position, for later use with [`rewind`].
+    ///
+    /// The returned value is the number of bytes currently written and is always a
+    /// valid UTF-8 boundary, so it can be handed back to [`rewind`] to undo any
+    /// writes performed in the meantime.
+    ///
+    /// [`rewind`]: StrBuf::rewind
+    pub fn checkpoint(&self) -> usize {
+        self.1
+    }
+
+    /// Restores the cursor to a position previously returned by [`checkpoint`].
+    ///
+    /// This discards everything written after `pos` without touching the underlying
+    /// bytes, which remain as scratch space for subsequent writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `core::fmt::Error` if `pos` is greater than the current length, does not
+    /// fall on a UTF-8 character boundary, or there is an unclosed [`span_start`] (a
+    /// rewind past an open span would leave it starting beyond the cursor). Completed
+    /// spans whose range extends past `pos` are dropped.
+    ///
+    /// [`checkpoint`]: StrBuf::checkpoint
+    /// [`span_start`]: StrBuf::span_start
+    pub fn rewind(&mut self, pos: usize) -> core::fmt::Result {
+        if pos > self.1 || !self.is_char_boundary(pos) || self.4 != 0 {
+            return Err(core::fmt::Error);
+        }
+
+        self.1 = pos;
+        self.drop_spans_past(pos);
+        Ok(())
+    }
+
+    /// Writes formatted arguments atomically, leaving the buffer untouched on overflow.
+    ///
+    /// Because `write_fmt` may emit several [`write_str`] calls for a single format, a
+    /// format that overflows partway would otherwise leave a half-written fragment in
+    /// the buffer. This method snapshots the cursor before formatting and restores the
+    /// cursor to it on error, so the write is all-or-nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `core::fmt::Error` if the formatted output does not fit; in that case
+    /// the buffer is left exactly as it was before the call.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use strbuf::StrBuf;
+    ///
+    /// let mut buf = StrBuf::<8>::default();
+    /// assert!(buf.try_format(format_args!("value={}", 123456)).is_err());
+    /// assert_eq!(buf.as_ref(), "");
+    /// ```
+    ///
+    /// [`write_str`]: core::fmt::Write::write_str
+    pub fn try_format(&mut self, args: core::fmt::Arguments<'_>) -> core::fmt::Result {
+        let pos = self.checkpoint();
+        self.write_fmt(args).inspect_err(|_| self.1 = pos)
+    }
+
+    /// Returns the total capacity of the buffer in bytes.
+    ///
+    /// This is the const generic `N` and never changes for a given buffer.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of unused bytes remaining in the buffer.
+    pub fn remaining(&self) -> usize {
+        N - self.1
+    }
+
+    /// Resets the buffer to empty, discarding all written bytes and any recorded spans.
+    ///
+    /// Both completed and open spans are cleared, so a reused buffer starts its span
+    /// table afresh.
+    pub fn clear(&mut self) {
+        self.1 = 0;
+        self.3 = 0;
+        self.4 = 0;
+    }
+
+    /// Shortens the buffer to `len` bytes, discarding any bytes beyond it.
+    ///
+    /// If `len` is greater than or equal to the current length the buffer is left
+    /// unchanged, mirroring `alloc::string::String::truncate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `core::fmt::Error` if `len` does not fall on a UTF-8 character boundary,
+    /// or there is an unclosed [`span_start`]. Completed spans whose range extends past
+    /// `len` are dropped.
+    ///
+    /// [`span_start`]: StrBuf::span_start
+    pub fn truncate(&mut self, len: usize) -> core::fmt::Result {
+        if len >= self.1 {
+            return Ok(());
+        }
+
+        if !self.is_char_boundary(len) || self.4 != 0 {
+            return Err(core::fmt::Error);
+        }
+
+        self.1 = len;
+        self.drop_spans_past(len);
+        Ok(())
+    }
+
+    /// Drops completed spans whose end offset lies beyond `len`, compacting the table.
+    fn drop_spans_past(&mut self, len: usize) {
+        let mut w = 0;
+        let mut r = 0;
+        while r < self.3 {
+            if self.2[r].end <= len {
+                self.2[w] = self.2[r];
+                w += 1;
+            }
+            r += 1;
+        }
+        self.3 = w;
+    }
+
+    /// Writes as much of `s` as fits, truncating on a character boundary.
+    ///
+    /// Unlike the [`write_str`] impl, this never errors: it copies the longest prefix
+    /// of `s` whose whole characters fit in the [`remaining`] capacity and returns the
+    /// number of bytes actually written. A multi-byte codepoint is never split. This
+    /// suits log and telemetry paths that prefer a clipped message over a dropped one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use strbuf::StrBuf;
+    ///
+    /// let mut buf = StrBuf::<4>::default();
+    /// // "é" is two bytes, so "x" would not fit and is dropped whole.
+    /// assert_eq!(buf.write_truncating("abéxy"), 4);
+    /// assert_eq!(buf.as_ref(), "abé");
+    /// ```
+    ///
+    /// [`write_str`]: core::fmt::Write::write_str
+    /// [`remaining`]: StrBuf::remaining
+    pub fn write_truncating(&mut self, s: &str) -> usize {
+        let remaining = self.remaining();
+        let mut end = 0;
+        for (idx, ch) in s.char_indices() {
+            let next = idx + ch.len_utf8();
+            if next > remaining {
+                break;
+            }
+            end = next;
+        }
+
+        let _ = self.write_str(&s[..end]);
+        end
+    }
+
+    /// Writes `s` quoted and escaped exactly as Rust's `{:?}` formatter renders a
+    /// `&str`.
+    ///
+    /// The string is surrounded by `"` and has `"`, `\`, newline, tab, carriage
+    /// return, and other control characters escaped. Escaping happens in a single
+    /// streaming pass over the `char`s, so no intermediate `String` is allocated. Each
+    /// escape is capacity-checked as a unit before being written, matching the
+    /// overflow semantics of the [`write_str`] impl; the closing quote is appended only
+    /// if it fits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `core::fmt::Error` if the quoted, escaped form does not fit. Unlike a
+    /// single [`write_str`] call, a partial write may remain: the opening quote and any
+    /// escapes written before the overflow stay in the buffer. Pair it with
+    /// [`try_format`] or a [`checkpoint`]/[`rewind`] pair for all-or-nothing behaviour.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use strbuf::StrBuf;
+    ///
+    /// let mut buf = StrBuf::<16>::default();
+    /// buf.write_str_debug("a\tb").unwrap();
+    /// assert_eq!(buf.as_ref(), r#""a\tb""#);
+    /// ```
+    ///
+    /// [`write_str`]: core::fmt::Write::write_str
+    /// [`try_format`]: StrBuf::try_format
+    /// [`checkpoint`]: StrBuf::checkpoint
+    /// [`rewind`]: StrBuf::rewind
+    pub fn write_str_debug(&mut self, s: &str) -> core::fmt::Result {
+        self.write_str("\"")?;
+        for ch in s.chars() {
+            // `char::escape_debug` matches `str`'s `{:?}` for every character except
+            // the apostrophe, which a string literal leaves unescaped.
+            if ch == '\'' {
+                self.write_str("'")?;
+                continue;
+            }
+
+            // The longest escape (`\u{10ffff}`) is ten bytes, so this never overflows.
+            let mut esc = StrBuf::<16>::new();
+            for e in ch.escape_debug() {
+                let mut tmp = [0u8; 4];
+                let _ = esc.write_str(e.encode_utf8(&mut tmp));
+            }
+            self.write_str(esc.as_str())?;
+        }
+        self.write_str("\"")
+    }
+
+    /// Borrows the buffer as a NUL-terminated C string for FFI.
+    ///
+    /// A trailing NUL byte is written into the buffer's spare capacity without
+    /// advancing the logical length, so the returned [`CStr`] stays valid only until
+    /// the next write. This lets a `StrBuf` double as a scratch buffer for syscalls or
+    /// other C APIs without a separate allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CStrError::InteriorNul`] if the written contents already contain a
+    /// `\0`, or [`CStrError::NoCapacity`] if there is no room for the terminator.
+    ///
+    /// [`CStr`]: core::ffi::CStr
+    pub fn as_c_str(&mut self) -> Result<&core::ffi::CStr, CStrError> {
+        if self.as_bytes().contains(&0) {
+            return Err(CStrError::InteriorNul);
+        }
+
+        if self.1 >= N {
+            return Err(CStrError::NoCapacity);
+        }
+
+        self.0[self.1] = 0;
+        core::ffi::CStr::from_bytes_with_nul(&self.0[..=self.1])
+            .map_err(|_| CStrError::InteriorNul)
+    }
+
+    /// Opens a span tagged with `tag` at the current cursor position.
+    ///
+    /// Spans carry styling information (a terminal color, a syntax-highlight class,
+    /// ...) out of band, so the formatted payload stays free of markup bytes. Each
+    /// open span must be closed with [`span_end`]; spans may nest but must be closed
+    /// in strict FILO order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpanError::Overflow`] if the span table (capacity `S`) has no room for
+    /// another entry.
+    ///
+    /// [`span_end`]: StrBuf::span_end
+    pub fn span_start(&mut self, tag: u16) -> Result<(), SpanError> {
+        if self.3 + self.4 >= S {
+            return Err(SpanError::Overflow);
+        }
+
+        self.4 += 1;
+        self.2[S - self.4] = Span {
+            tag,
+            start: self.1,
+            end: self.1,
+        };
+        Ok(())
+    }
+
+    /// Closes the most recently opened span, recording its byte range.
+    ///
+    /// The span's end is set to the current cursor position and the completed
+    /// `(tag, start, end)` entry becomes visible via [`spans`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpanError::Unbalanced`] if there is no open span to close.
+    ///
+    /// [`spans`]: StrBuf::spans
+    pub fn span_end(&mut self) -> Result<(), SpanError> {
+        if self.4 == 0 {
+            return Err(SpanError::Unbalanced);
+        }
+
+        let mut span = self.2[S - self.4];
+        self.4 -= 1;
+        span.end = self.1;
+        self.2[self.3] = span;
+        self.3 += 1;
+        Ok(())
+    }
+
+    /// Returns the completed spans in the order they were closed.
+    ///
+    /// A renderer can post-process the flat [`as_ref`] string together with this list
+    /// to apply styling without the escape codes ever touching the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use strbuf::StrBuf;
+    /// use core::fmt::Write;
+    ///
+    /// let mut buf = StrBuf::<32, 4>::default();
+    /// write!(buf, "a=").unwrap();
+    /// buf.span_start(1).unwrap();
+    /// write!(buf, "42").unwrap();
+    /// buf.span_end().unwrap();
+    ///
+    /// assert_eq!(buf.as_ref(), "a=42");
+    /// assert_eq!(buf.spans()[0].tag, 1);
+    /// assert_eq!((buf.spans()[0].start, buf.spans()[0].end), (2, 4));
+    /// ```
+    ///
+    /// [`as_ref`]: StrBuf::as_ref
+    pub fn spans(&self) -> &[Span] {
+        &self.2[..self.3]
+    }
 }
+
+/// An out-of-band styling annotation over a byte range of a [`StrBuf`].
+///
+/// Spans are recorded by [`StrBuf::span_start`]/[`StrBuf::span_end`] and retrieved via
+/// [`StrBuf::spans`]. The byte range is half-open: `start..end` indexes the flat
+/// written string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// The caller-chosen tag identifying how to style this region.
+    pub tag: u16,
+    /// The inclusive start byte offset of the span.
+    pub start: usize,
+    /// The exclusive end byte offset of the span.
+    pub end: usize,
+}
+
+impl Span {
+    /// A zeroed span used to initialize unused table slots.
+    const EMPTY: Span = Span {
+        tag: 0,
+        start: 0,
+        end: 0,
+    };
+}
+
+/// The error returned by [`StrBuf::span_start`] and [`StrBuf::span_end`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpanError {
+    /// The span table is full; no further spans can be opened.
+    Overflow,
+
+    /// A span was closed without a matching open span.
+    Unbalanced,
+}
+
+impl core::fmt::Display for SpanError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overflow => f.write_str("span table capacity exceeded"),
+            Self::Unbalanced => f.write_str("unbalanced span_end"),
+        }
+    }
+}
+
+impl core::error::Error for SpanError {}
+
+/// The error returned by [`StrBuf::as_c_str`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CStrError {
+    /// The written contents contain an interior `\0`, which cannot be represented as a
+    /// C string.
+    InteriorNul,
+
+    /// There is no spare capacity left for the trailing NUL terminator.
+    NoCapacity,
+}
+
+impl core::fmt::Display for CStrError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InteriorNul => f.write_str("interior NUL byte in buffer contents"),
+            Self::NoCapacity => f.write_str("no capacity for NUL terminator"),
+        }
+    }
+}
+
+impl core::error::Error for CStrError {}